@@ -1,106 +1,98 @@
-//! The main thread produces history by creating new ticks.
-//! A second thread runs the verifier.
-//! The tick producer sends the verifier blocks of ticks (1M) for verification.
-//! After 10 blocks, the producer stops, the threads synchronise, and the
-//! producer and verifier sanity-check their history.
+//! Produces history using a `Historian`, which ticks continuously on a background thread and
+//! only needs to be given the hash of some event data, here `BLOCK * 10 / 16` events sent as
+//! fast as a separate thread can hash them.
+//!
+//! A second thread runs the verifier, consuming blocks of the resulting `Entry`s and checking
+//! that each follows from the last via `verify_entry`. Once every event has been turned into an
+//! entry, the producer stops, the threads synchronise, and both sides confirm they reached the
+//! same final hash.
 //! This demo needs to run on a system with at least 2 cores, or the verifier will fall behind.
 
 use digest::Digest;
-use std::collections::HashMap;
+use proof_of_history::Entry;
 
 type Hasher = sha3::Keccak256;
-type Hash = digest::Output<Hasher>;
 
-struct Block {
-    ticks: Vec<Hash>,
-    input_data: HashMap<Hash, String>,
-}
-
-// Number of ticks in a block.
-const BLOCK: usize = 1_000_000;
+// Approximate number of ticks per block.
+const BLOCK: u64 = 1_000_000;
 
 fn main() {
-    let (tx, rx) = std::sync::mpsc::sync_channel::<Block>(1);
+    let (tx, rx) = std::sync::mpsc::sync_channel::<Vec<Entry<Hasher>>>(1);
     let seed_data = "Hello World!";
     let seed = Hasher::digest(seed_data.as_bytes());
     let start = std::time::Instant::now();
 
     // Run the verifier on a separate thread.
     let verifier = std::thread::spawn(move || {
-        let mut history: Vec<Hash> = vec![seed];
-        let mut data: HashMap<Hash, String> = HashMap::default();
+        let mut hash = seed;
+        let mut num_hashes = 0u64;
         for block in rx {
             let block_ts = std::time::Instant::now();
-            // The closure used to map the tick hash to its input data.
-            let data_fn = |_ix, hash: &Hash| {
-                block
-                    .input_data
-                    .get(hash)
-                    .map(Hasher::digest)
-                    .unwrap_or_default()
-            };
-            // Verify the start of the block is valid given the last tick.
-            if let (Some(last), Some(first)) = (history.last(), block.ticks.first()) {
-                let ticks = &[last.clone(), first.clone()];
-                proof_of_history::verify::<Hasher, _>(ticks, &data_fn).unwrap();
+            for entry in &block {
+                assert!(proof_of_history::verify_entry::<Hasher>(&hash, entry));
+                hash = entry.end_hash;
+                num_hashes += entry.num_hashes;
             }
-            // Verify the block.
-            proof_of_history::verify::<Hasher, _>(&block.ticks, &data_fn).unwrap();
             println!(
-                "{:?}: Verified block {}..{} in {:?}",
+                "{:?}: Verified block ending at tick {num_hashes} in {:?}",
                 start.elapsed(),
-                history.len() - 1,
-                history.len() - 1 + BLOCK,
                 block_ts.elapsed(),
             );
-            history.extend(block.ticks);
-            data.extend(block.input_data);
         }
-        history
+        (hash, num_hashes)
     });
 
-    // Produce 10 blocks of ticks.
-    let mut history = vec![seed];
-    let mut ticks = proof_of_history::ticks::<Hasher>(seed);
-    let mut block_ts = std::time::Instant::now();
-    let mut input_data: HashMap<Hash, String> = HashMap::default();
-    for i in 0..(BLOCK * 10) {
-        // Add some data to every 16th tick.
-        let tick = match (i + 1) % 16 {
-            0 => {
-                // When running PoH in production, the tick thread should be
-                // doing as little work as possible besides producing ticks,
-                // but for now we construct data on the same thread just for
-                // the demo!
-                let tick_data = format!("Extra data for tick {i}");
-                let tick_data_hash = Hasher::digest(&tick_data);
-                let tick = ticks.next_with_data(&tick_data_hash);
-                input_data.insert(tick, tick_data);
-                tick
+    // Produce history via a `Historian`, which ticks continuously on its own thread - the
+    // producer only ever has to submit the hash of some event data, keeping the tick thread
+    // free to do as little else as possible.
+    let historian = proof_of_history::historian::<Hasher>(seed);
+
+    // Feed the historian `BLOCK * 10 / 16` events from a separate thread so hashing each
+    // event's data never competes with the tick thread for CPU time. Events are sent as fast
+    // as this thread can hash them; nothing spaces them a fixed number of ticks apart.
+    let event_sender = historian.event_sender;
+    std::thread::spawn(move || {
+        for i in 0..(BLOCK * 10 / 16) {
+            let tick_data = format!("Extra data for tick {i}");
+            let tick_data_hash = Hasher::digest(tick_data);
+            if event_sender.send(tick_data_hash).is_err() {
+                break;
             }
-            _ => ticks.next(),
-        };
-        history.push(tick);
-        if i > 0 && (i + 1) % BLOCK == 0 {
+        }
+    });
+
+    // Collect entries into blocks of roughly `BLOCK` ticks, forwarding each to the verifier.
+    let mut block = Vec::new();
+    let mut block_num_hashes = 0u64;
+    let mut block_ts = std::time::Instant::now();
+    let mut total_num_hashes = 0u64;
+    let mut final_hash = seed;
+    for entry in historian.entry_receiver {
+        block_num_hashes += entry.num_hashes;
+        total_num_hashes += entry.num_hashes;
+        final_hash = entry.end_hash;
+        block.push(entry);
+        if block_num_hashes >= BLOCK {
             println!(
-                "{:?}: Produced block {}..{} in {:?}",
+                "{:?}: Produced block of {block_num_hashes} ticks in {:?}",
                 start.elapsed(),
-                history.len() - 1 - BLOCK,
-                history.len() - 1,
                 block_ts.elapsed(),
             );
-            let ticks = history[history.len() - BLOCK..].to_vec();
-            let block = Block { ticks, input_data };
-            tx.send(block).expect("Verifier fell behind!");
+            tx.send(std::mem::take(&mut block))
+                .expect("Verifier fell behind!");
             block_ts = std::time::Instant::now();
-            input_data = HashMap::default();
+            block_num_hashes = 0;
         }
     }
+    if !block.is_empty() {
+        tx.send(block).expect("Verifier fell behind!");
+    }
 
     // Let the verifier begin processing the last block before dropping the channel.
     std::thread::sleep(std::time::Duration::from_secs(1));
     std::mem::drop(tx);
-    let verifier_history = verifier.join().unwrap();
+    let (verifier_hash, verifier_num_hashes) = verifier.join().unwrap();
 
-    assert_eq!(history, verifier_history);
+    assert_eq!(final_hash, verifier_hash);
+    assert_eq!(total_num_hashes, verifier_num_hashes);
 }
@@ -0,0 +1,138 @@
+//! Compressed entries for runs of ticks that carry no data.
+
+use crate::tick;
+use digest::{Digest, Output};
+
+/// A compressed record of `num_hashes` consecutive ticks, ending in `end_hash`.
+///
+/// Rather than storing every intermediate tick hash, an `Entry` stores only the number of
+/// ticks that elapsed since the previous entry and the resulting hash, optionally mixing in a
+/// single piece of `data` on the final tick. This mirrors Solana's original Proof of History
+/// `Entry` design and keeps the footprint of a recorded history proportional to the number of
+/// data-bearing events rather than to the raw tick count.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Output<D>: serde::Serialize",
+        deserialize = "Output<D>: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Entry<D: Digest> {
+    /// The number of ticks elapsed since the previous entry.
+    pub num_hashes: u64,
+    /// The hash produced by the final tick in this entry's run.
+    pub end_hash: Output<D>,
+    /// The data mixed into the final tick, if any.
+    pub data: Option<Output<D>>,
+}
+
+// Derived `PartialEq`/`Eq` would add a spurious `D: PartialEq`/`D: Eq` bound, even though `D`
+// is only ever used via `Output<D>`, which real digest types (e.g. `sha2::Sha256`) don't
+// satisfy. Compare the fields directly instead.
+impl<D: Digest> PartialEq for Entry<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_hashes == other.num_hashes
+            && self.end_hash == other.end_hash
+            && self.data == other.data
+    }
+}
+
+impl<D: Digest> Eq for Entry<D> {}
+
+/// Produces the next [`Entry`] by ticking `num_hashes` times from `seed`, mixing `data` into
+/// the final tick if provided and leaving every earlier tick in the run empty.
+///
+/// When `data` is `None` this is equivalent to calling [`tick`][crate::tick] `num_hashes` times
+/// with empty data. When `num_hashes` is `1`, this is equivalent to a single call to
+/// [`tick`][crate::tick] with `data` (or empty data, if `data` is `None`).
+///
+/// # Panics
+///
+/// Panics if `num_hashes` is `0`, as an entry must cover at least one tick.
+pub fn next_entry<D: Digest>(seed: &Output<D>, num_hashes: u64, data: Option<Output<D>>) -> Entry<D> {
+    assert!(num_hashes > 0, "an entry must cover at least one tick");
+    let empty = Output::<D>::default();
+    let mut hash = seed.clone();
+    for _ in 0..num_hashes - 1 {
+        hash = tick::<D>(&hash, &empty);
+    }
+    hash = tick::<D>(&hash, data.as_ref().unwrap_or(&empty));
+    Entry {
+        num_hashes,
+        end_hash: hash,
+        data,
+    }
+}
+
+/// Verifies that `entry` correctly follows from `start_hash`.
+///
+/// Recomputes the chain by hashing `start_hash` forward `num_hashes` times with empty data,
+/// mixing in `entry.data` on the final hash, and comparing the result against `entry.end_hash`.
+///
+/// Returns `false` (rather than panicking) if `entry.num_hashes` is `0`, since an entry must
+/// cover at least one tick and `entry` may come from untrusted ledger or network data.
+pub fn verify_entry<D: Digest>(start_hash: &Output<D>, entry: &Entry<D>) -> bool {
+    if entry.num_hashes == 0 {
+        return false;
+    }
+    let empty = Output::<D>::default();
+    let mut hash = start_hash.clone();
+    for _ in 0..entry.num_hashes - 1 {
+        hash = tick::<D>(&hash, &empty);
+    }
+    hash = tick::<D>(&hash, entry.data.as_ref().unwrap_or(&empty));
+    hash == entry.end_hash
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entry_roundtrip() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let data = Hasher::digest(b"hello");
+        let entry = next_entry::<Hasher>(&seed, 16, Some(data));
+        assert!(verify_entry::<Hasher>(&seed, &entry));
+    }
+
+    #[test]
+    fn test_entry_degenerate_case_matches_tick() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let data = Hasher::digest(b"hello");
+
+        // With no data, a single-hash entry matches a plain `tick` over empty data.
+        let empty = Output::<Hasher>::default();
+        let no_data_entry = next_entry::<Hasher>(&seed, 1, None);
+        assert_eq!(no_data_entry.end_hash, tick::<Hasher>(&seed, &empty));
+
+        // With data, a single-hash entry matches a plain `tick` mixing in that data.
+        let data_entry = next_entry::<Hasher>(&seed, 1, Some(data.clone()));
+        assert_eq!(data_entry.end_hash, tick::<Hasher>(&seed, &data));
+    }
+
+    #[test]
+    fn test_verify_entry_rejects_zero_num_hashes() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let entry = Entry::<Hasher> {
+            num_hashes: 0,
+            end_hash: seed.clone(),
+            data: None,
+        };
+        assert!(!verify_entry::<Hasher>(&seed, &entry));
+    }
+
+    #[test]
+    fn test_entry_rejects_wrong_end_hash() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let mut entry = next_entry::<Hasher>(&seed, 16, None);
+        entry.end_hash = Hasher::digest(b"tampered");
+        assert!(!verify_entry::<Hasher>(&seed, &entry));
+    }
+}
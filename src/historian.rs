@@ -0,0 +1,94 @@
+//! A background service that produces ticks continuously and tags incoming data with entries.
+
+use crate::{ticks, Entry};
+use digest::{Digest, Output};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread::JoinHandle;
+
+/// Runs a [`Ticks`][crate::Ticks] loop on a dedicated thread, accepting data to mix into the
+/// tick stream and emitting the resulting [`Entry`]s.
+///
+/// Application code only needs to submit event data via `event_sender` and read the resulting,
+/// ordered stream of entries from `entry_receiver` - all hashing happens off the caller's
+/// thread, which keeps the tick thread free to do as little else as possible.
+pub struct Historian<D: Digest> {
+    /// Submit the hash of some event data to be mixed into the next tick.
+    pub event_sender: Sender<Output<D>>,
+    /// Receive the stream of entries produced as event data arrives.
+    pub entry_receiver: Receiver<Entry<D>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<D: Digest> Historian<D> {
+    /// Drops `event_sender` and blocks until the tick thread has stopped in response.
+    pub fn join(self) -> std::thread::Result<()> {
+        let Historian {
+            event_sender,
+            thread,
+            ..
+        } = self;
+        std::mem::drop(event_sender);
+        thread.expect("thread already joined").join()
+    }
+}
+
+/// Spawns a [`Historian`] that ticks continuously from `seed` on its own thread.
+pub fn historian<D>(seed: Output<D>) -> Historian<D>
+where
+    D: Digest + Send + 'static,
+    Output<D>: Send,
+{
+    let (event_sender, event_receiver) = mpsc::channel::<Output<D>>();
+    let (entry_sender, entry_receiver) = mpsc::channel::<Entry<D>>();
+    let thread = std::thread::spawn(move || {
+        let mut ticks = ticks::<D>(seed);
+        let mut num_hashes: u64 = 0;
+        loop {
+            match event_receiver.try_recv() {
+                Ok(data) => {
+                    num_hashes += 1;
+                    let end_hash = ticks.next_with_data(&data);
+                    let entry = Entry {
+                        num_hashes,
+                        end_hash,
+                        data: Some(data),
+                    };
+                    num_hashes = 0;
+                    if entry_sender.send(entry).is_err() {
+                        break;
+                    }
+                }
+                Err(TryRecvError::Empty) => {
+                    ticks.next();
+                    num_hashes += 1;
+                }
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+    });
+    Historian {
+        event_sender,
+        entry_receiver,
+        thread: Some(thread),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_historian_tags_events() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let historian = historian::<Hasher>(seed.clone());
+
+        let data = Hasher::digest(b"an event");
+        historian.event_sender.send(data.clone()).unwrap();
+        let entry = historian.entry_receiver.recv().unwrap();
+        assert_eq!(entry.data, Some(data));
+        assert!(crate::verify_entry::<Hasher>(&seed, &entry));
+
+        historian.join().unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+//! A self-contained, binary ledger file format for persisting and replaying a stream of
+//! [`Entry`]s across processes.
+//!
+//! Each ledger opens with a small header - magic bytes, the name of the [`Digest`] algorithm
+//! used to produce it, and its digest length in bytes - so that a reader can confirm it is
+//! using a matching `Digest` before attempting to decode any entries. Entries themselves are
+//! encoded with `bincode`, each prefixed by its length so the stream can be read back one
+//! entry at a time.
+
+use crate::{verify_entry, Entry};
+use digest::{Digest, Output};
+use std::io::{self, Read, Write};
+
+/// Identifies this crate's ledger format and its version.
+const MAGIC: &[u8; 4] = b"POH1";
+
+/// The largest permitted encoded length of a single entry, guarding against a corrupt or
+/// malicious length prefix driving an unbounded allocation while reading an untrusted ledger
+/// or live network feed.
+const MAX_ENTRY_LEN: u32 = 1 << 20;
+
+/// Error produced while writing, reading, or verifying a ledger.
+#[derive(Debug)]
+pub enum LedgerError {
+    /// Reading from or writing to the underlying stream failed.
+    Io(io::Error),
+    /// The stream did not begin with this format's magic bytes.
+    BadMagic,
+    /// The ledger's header named a different digest algorithm than expected.
+    AlgorithmMismatch {
+        /// The algorithm name the caller expected.
+        expected: String,
+        /// The algorithm name found in the ledger's header.
+        found: String,
+    },
+    /// The ledger's header named a different digest length than `D` produces.
+    DigestLenMismatch {
+        /// The digest length, in bytes, that `D` produces.
+        expected: u16,
+        /// The digest length, in bytes, found in the ledger's header.
+        found: u16,
+    },
+    /// An entry could not be encoded while writing.
+    Encode(bincode::Error),
+    /// An entry's bytes could not be decoded while reading.
+    Decode(bincode::Error),
+    /// An entry's length prefix exceeded [`MAX_ENTRY_LEN`].
+    EntryTooLarge {
+        /// The length prefix read from the stream.
+        len: u32,
+        /// The largest length prefix this crate will allocate for.
+        max: u32,
+    },
+    /// The entry at this index failed verification against the previous entry's `end_hash`.
+    Invalid(usize),
+}
+
+impl From<io::Error> for LedgerError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl std::fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "ledger IO error: {e}"),
+            Self::BadMagic => write!(f, "stream is not a proof-of-history ledger"),
+            Self::AlgorithmMismatch { expected, found } => write!(
+                f,
+                "ledger was written with digest algorithm \"{found}\", expected \"{expected}\""
+            ),
+            Self::DigestLenMismatch { expected, found } => write!(
+                f,
+                "ledger's digest length is {found} bytes, expected {expected}"
+            ),
+            Self::Encode(e) => write!(f, "failed to encode entry: {e}"),
+            Self::Decode(e) => write!(f, "failed to decode entry: {e}"),
+            Self::EntryTooLarge { len, max } => {
+                write!(f, "entry length {len} exceeds the maximum of {max} bytes")
+            }
+            Self::Invalid(ix) => write!(f, "entry {ix} failed verification"),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+fn write_header<D: Digest, W: Write>(writer: &mut W, algorithm: &str) -> io::Result<()> {
+    writer.write_all(MAGIC)?;
+    let alg_bytes = algorithm.as_bytes();
+    assert!(alg_bytes.len() <= u8::MAX as usize, "algorithm name too long");
+    writer.write_all(&[alg_bytes.len() as u8])?;
+    writer.write_all(alg_bytes)?;
+    let digest_len = Output::<D>::default().len() as u16;
+    writer.write_all(&digest_len.to_le_bytes())?;
+    Ok(())
+}
+
+fn read_header<D: Digest, R: Read>(
+    reader: &mut R,
+    expected_algorithm: &str,
+) -> Result<(), LedgerError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(LedgerError::BadMagic);
+    }
+    let mut alg_len = [0u8; 1];
+    reader.read_exact(&mut alg_len)?;
+    let mut alg_buf = vec![0u8; alg_len[0] as usize];
+    reader.read_exact(&mut alg_buf)?;
+    let algorithm = String::from_utf8_lossy(&alg_buf).into_owned();
+    if algorithm != expected_algorithm {
+        return Err(LedgerError::AlgorithmMismatch {
+            expected: expected_algorithm.to_string(),
+            found: algorithm,
+        });
+    }
+    let mut digest_len_buf = [0u8; 2];
+    reader.read_exact(&mut digest_len_buf)?;
+    let digest_len = u16::from_le_bytes(digest_len_buf);
+    let expected_len = Output::<D>::default().len() as u16;
+    if digest_len != expected_len {
+        return Err(LedgerError::DigestLenMismatch {
+            expected: expected_len,
+            found: digest_len,
+        });
+    }
+    Ok(())
+}
+
+fn write_entry<D: Digest, W: Write>(writer: &mut W, entry: &Entry<D>) -> Result<(), LedgerError>
+where
+    Entry<D>: serde::Serialize,
+{
+    let bytes = bincode::serialize(entry).map_err(LedgerError::Encode)?;
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Reads a single entry from `reader`, returning `None` once the stream is exhausted exactly
+/// on an entry boundary.
+fn read_entry<D: Digest, R: Read>(reader: &mut R) -> Result<Option<Entry<D>>, LedgerError>
+where
+    Entry<D>: for<'de> serde::Deserialize<'de>,
+{
+    let mut len_buf = [0u8; 4];
+    let mut filled = 0;
+    while filled < len_buf.len() {
+        match reader.read(&mut len_buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let len = u32::from_le_bytes(len_buf);
+    if len > MAX_ENTRY_LEN {
+        return Err(LedgerError::EntryTooLarge {
+            len,
+            max: MAX_ENTRY_LEN,
+        });
+    }
+    let mut buf = vec![0u8; len as usize];
+    reader.read_exact(&mut buf)?;
+    let entry = bincode::deserialize(&buf).map_err(LedgerError::Decode)?;
+    Ok(Some(entry))
+}
+
+/// Writes `entries` to `writer` as a self-contained ledger, framed with a header naming
+/// `algorithm` (e.g. `"sha2-256"`) as the digest used to produce them.
+pub fn write_ledger<D, W>(mut writer: W, algorithm: &str, entries: &[Entry<D>]) -> Result<(), LedgerError>
+where
+    D: Digest,
+    W: Write,
+    Entry<D>: serde::Serialize,
+{
+    write_header::<D, _>(&mut writer, algorithm)?;
+    for entry in entries {
+        write_entry(&mut writer, entry)?;
+    }
+    Ok(())
+}
+
+/// Reads every entry from a ledger previously written by [`write_ledger`], validating that its
+/// header names `expected_algorithm` and a digest length matching `D` before decoding.
+pub fn read_ledger<D, R>(mut reader: R, expected_algorithm: &str) -> Result<Vec<Entry<D>>, LedgerError>
+where
+    D: Digest,
+    R: Read,
+    Entry<D>: for<'de> serde::Deserialize<'de>,
+{
+    read_header::<D, _>(&mut reader, expected_algorithm)?;
+    let mut entries = Vec::new();
+    while let Some(entry) = read_entry::<D, _>(&mut reader)? {
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Reads and verifies a ledger in a single, bounded-memory pass: entries are decoded one at a
+/// time and checked against the chain's running hash as they arrive, in the same spirit as
+/// [`verify_stream`][crate::verify_stream], without ever materializing the whole ledger.
+pub fn verify_ledger<D, R>(
+    mut reader: R,
+    expected_algorithm: &str,
+    seed: &Output<D>,
+) -> Result<(), LedgerError>
+where
+    D: Digest,
+    R: Read,
+    Entry<D>: for<'de> serde::Deserialize<'de>,
+{
+    read_header::<D, _>(&mut reader, expected_algorithm)?;
+    let mut hash = seed.clone();
+    let mut ix = 0;
+    while let Some(entry) = read_entry::<D, _>(&mut reader)? {
+        if !verify_entry::<D>(&hash, &entry) {
+            return Err(LedgerError::Invalid(ix));
+        }
+        hash = entry.end_hash.clone();
+        ix += 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::next_entry;
+
+    #[test]
+    fn test_ledger_roundtrip() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let mut hash = seed.clone();
+        let entries: Vec<_> = (0..4)
+            .map(|i| {
+                let data = (i % 2 == 0).then(|| Hasher::digest(format!("data {i}")));
+                let entry = next_entry::<Hasher>(&hash, 16, data);
+                hash = entry.end_hash.clone();
+                entry
+            })
+            .collect();
+
+        let mut bytes = Vec::new();
+        write_ledger(&mut bytes, "sha2-256", &entries).unwrap();
+
+        let decoded = read_ledger::<Hasher, _>(&bytes[..], "sha2-256").unwrap();
+        assert_eq!(decoded, entries);
+
+        verify_ledger::<Hasher, _>(&bytes[..], "sha2-256", &seed).unwrap();
+    }
+
+    #[test]
+    fn test_read_ledger_rejects_algorithm_mismatch() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let entry = next_entry::<Hasher>(&seed, 4, None);
+        let mut bytes = Vec::new();
+        write_ledger(&mut bytes, "sha2-256", &[entry]).unwrap();
+
+        let err = read_ledger::<Hasher, _>(&bytes[..], "keccak256").unwrap_err();
+        assert!(matches!(err, LedgerError::AlgorithmMismatch { .. }));
+    }
+
+    #[test]
+    fn test_read_ledger_rejects_oversized_entry_len() {
+        type Hasher = sha2::Sha256;
+        let mut bytes = Vec::new();
+        write_header::<Hasher, _>(&mut bytes, "sha2-256").unwrap();
+        bytes.extend_from_slice(&(MAX_ENTRY_LEN + 1).to_le_bytes());
+
+        let err = read_ledger::<Hasher, _>(&bytes[..], "sha2-256").unwrap_err();
+        assert!(matches!(err, LedgerError::EntryTooLarge { .. }));
+    }
+
+    #[test]
+    fn test_verify_ledger_detects_tampering() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let mut entry = next_entry::<Hasher>(&seed, 16, None);
+        entry.end_hash = Hasher::digest(b"tampered");
+        let mut bytes = Vec::new();
+        write_ledger(&mut bytes, "sha2-256", &[entry]).unwrap();
+
+        let err = verify_ledger::<Hasher, _>(&bytes[..], "sha2-256", &seed).unwrap_err();
+        assert!(matches!(err, LedgerError::Invalid(0)));
+    }
+}
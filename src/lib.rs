@@ -4,6 +4,23 @@
 pub use digest;
 use digest::{Digest, Output};
 
+mod entry;
+pub use entry::{next_entry, verify_entry, Entry};
+
+mod historian;
+pub use historian::{historian, Historian};
+
+mod merkle;
+pub use merkle::{merkle_proof, merkle_root, next_with_batch, verify_membership, MerkleProof};
+
+mod stream;
+pub use stream::{verify_reader, verify_stream, VerifyReaderError};
+
+#[cfg(feature = "serde")]
+mod ledger;
+#[cfg(feature = "serde")]
+pub use ledger::{read_ledger, verify_ledger, write_ledger, LedgerError};
+
 /// A simple wrapper around the `tick` function that stores the output of the
 /// previous tick and automatically supplies it to the `tick` function on each call
 /// to `next` or `next_with_data`.
@@ -0,0 +1,212 @@
+//! Mixing a batch of data items into a single tick via a Merkle root.
+
+use crate::{tick, Ticks};
+use digest::{Digest, Output};
+
+/// A Merkle inclusion proof for a single item within a batch committed via
+/// [`next_with_batch`].
+///
+/// Stores the sibling hash at each level from the leaf up to the root, in ascending order.
+/// A `None` sibling means the node at that level was carried forward unchanged because its
+/// level had an odd number of nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof<D: Digest> {
+    /// The sibling (or lack thereof) at each level, from the leaf's level up to the root.
+    pub siblings: Vec<Option<Output<D>>>,
+    /// The index of the item within the original, leaf-ordered batch.
+    pub index: usize,
+}
+
+/// Domain-separation prefix for leaf hashes, distinguishing them from internal-node hashes so
+/// that an internal node's hash can never be replayed as a forged leaf (cf. RFC 6962 §2.1).
+const LEAF_PREFIX: [u8; 1] = [0x00];
+/// Domain-separation prefix for internal-node hashes.
+const NODE_PREFIX: [u8; 1] = [0x01];
+
+/// Hashes a leaf item into the tree's leaf domain.
+fn hash_leaf<D: Digest>(item: &Output<D>) -> Output<D> {
+    let mut digest = D::new();
+    digest.update(LEAF_PREFIX);
+    digest.update(item);
+    digest.finalize()
+}
+
+/// Combines two adjacent node hashes into their parent, as used throughout the Merkle tree.
+fn hash_pair<D: Digest>(a: &Output<D>, b: &Output<D>) -> Output<D> {
+    let mut digest = D::new();
+    digest.update(NODE_PREFIX);
+    digest.update(a);
+    digest.update(b);
+    digest.finalize()
+}
+
+/// Builds every level of the Merkle tree over `items`, from the hashed leaves up to the
+/// single-node root level. A level with an odd number of nodes carries its final node forward
+/// unchanged rather than duplicating it.
+fn merkle_levels<D: Digest>(items: &[Output<D>]) -> Vec<Vec<Output<D>>> {
+    let leaves: Vec<_> = items.iter().map(hash_leaf::<D>).collect();
+    let mut levels = vec![leaves];
+    while levels.last().expect("levels is never empty").len() > 1 {
+        let prev = levels.last().expect("levels is never empty");
+        let next = prev
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair::<D>(a, b),
+                [a] => a.clone(),
+                _ => unreachable!("chunks(2) never yields an empty slice"),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Computes the Merkle root over `items`.
+///
+/// Returns the default, all-zero `Output<D>` if `items` is empty.
+pub fn merkle_root<D: Digest>(items: &[Output<D>]) -> Output<D> {
+    if items.is_empty() {
+        return Output::<D>::default();
+    }
+    merkle_levels::<D>(items)
+        .pop()
+        .expect("levels is never empty")
+        .remove(0)
+}
+
+/// Builds a [`MerkleProof`] that `items[index]` was included in the batch committed by
+/// [`merkle_root`]. Returns `None` if `index` is out of bounds.
+pub fn merkle_proof<D: Digest>(items: &[Output<D>], index: usize) -> Option<MerkleProof<D>> {
+    if index >= items.len() {
+        return None;
+    }
+    let levels = merkle_levels::<D>(items);
+    let mut siblings = Vec::with_capacity(levels.len() - 1);
+    let mut ix = index;
+    for level in &levels[..levels.len() - 1] {
+        let sibling_ix = if ix.is_multiple_of(2) { ix + 1 } else { ix - 1 };
+        siblings.push(level.get(sibling_ix).cloned());
+        ix /= 2;
+    }
+    Some(MerkleProof { siblings, index })
+}
+
+/// Verifies that `item` was committed at `proof.index` under `root`, without needing access to
+/// any of the other items in the batch.
+pub fn verify_membership<D: Digest>(root: &Output<D>, item: &Output<D>, proof: &MerkleProof<D>) -> bool {
+    let mut hash = hash_leaf::<D>(item);
+    let mut ix = proof.index;
+    for sibling in &proof.siblings {
+        hash = match sibling {
+            Some(sibling) if ix.is_multiple_of(2) => hash_pair::<D>(&hash, sibling),
+            Some(sibling) => hash_pair::<D>(sibling, &hash),
+            None => hash,
+        };
+        ix /= 2;
+    }
+    hash == *root
+}
+
+impl<D: Digest> Ticks<D> {
+    /// Mixes a batch of data item hashes into the next tick by committing to their Merkle
+    /// root, returning both the resulting tick hash and the root.
+    ///
+    /// This allows many items (e.g. a batch of transactions) to be associated with a single
+    /// tick while still letting a client later prove that a specific item was part of the
+    /// batch via [`merkle_proof`] and [`verify_membership`], without needing the rest of the
+    /// batch.
+    pub fn next_with_batch<I>(&mut self, items: I) -> (Output<D>, Output<D>)
+    where
+        I: IntoIterator<Item = Output<D>>,
+    {
+        let items: Vec<_> = items.into_iter().collect();
+        let root = merkle_root::<D>(&items);
+        let hash = self.next_with_data(&root);
+        (hash, root)
+    }
+}
+
+/// Computes the Merkle root over `items` and mixes it into `seed` as a single tick, without
+/// needing a [`Ticks`] instance. See [`Ticks::next_with_batch`] for the stateful equivalent.
+pub fn next_with_batch<D, I>(seed: &Output<D>, items: I) -> (Output<D>, Output<D>)
+where
+    D: Digest,
+    I: IntoIterator<Item = Output<D>>,
+{
+    let items: Vec<_> = items.into_iter().collect();
+    let root = merkle_root::<D>(&items);
+    (tick::<D>(seed, &root), root)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn items<D: Digest>(n: usize) -> Vec<Output<D>> {
+        (0..n).map(|i| D::digest(i.to_be_bytes())).collect()
+    }
+
+    #[test]
+    fn test_merkle_proof_power_of_two() {
+        type Hasher = sha2::Sha256;
+        let items = items::<Hasher>(8);
+        let root = merkle_root::<Hasher>(&items);
+        for (ix, item) in items.iter().enumerate() {
+            let proof = merkle_proof::<Hasher>(&items, ix).unwrap();
+            assert!(verify_membership::<Hasher>(&root, item, &proof));
+        }
+    }
+
+    #[test]
+    fn test_merkle_proof_odd_count() {
+        type Hasher = sha2::Sha256;
+        let items = items::<Hasher>(5);
+        let root = merkle_root::<Hasher>(&items);
+        for (ix, item) in items.iter().enumerate() {
+            let proof = merkle_proof::<Hasher>(&items, ix).unwrap();
+            assert!(verify_membership::<Hasher>(&root, item, &proof));
+        }
+    }
+
+    #[test]
+    fn test_merkle_proof_rejects_wrong_item() {
+        type Hasher = sha2::Sha256;
+        let items = items::<Hasher>(4);
+        let root = merkle_root::<Hasher>(&items);
+        let proof = merkle_proof::<Hasher>(&items, 1).unwrap();
+        let wrong = Hasher::digest(b"not in the batch");
+        assert!(!verify_membership::<Hasher>(&root, &wrong, &proof));
+    }
+
+    #[test]
+    fn test_merkle_proof_rejects_internal_node_as_forged_leaf() {
+        type Hasher = sha2::Sha256;
+        let items = items::<Hasher>(4);
+        let root = merkle_root::<Hasher>(&items);
+
+        // An internal node's hash must not verify as a leaf under a shortened sibling path,
+        // even though plain, non-domain-separated hashing would let it.
+        let levels = merkle_levels::<Hasher>(&items);
+        let internal_node = levels[1][0].clone();
+        let forged_proof = MerkleProof {
+            siblings: vec![levels[1].get(1).cloned()],
+            index: 0,
+        };
+        assert!(!verify_membership::<Hasher>(
+            &root,
+            &internal_node,
+            &forged_proof
+        ));
+    }
+
+    #[test]
+    fn test_next_with_batch_verifies() {
+        type Hasher = sha2::Sha256;
+        let seed = Output::<Hasher>::default();
+        let mut ticks = crate::ticks::<Hasher>(seed.clone());
+        let batch = items::<Hasher>(4);
+        let (hash, root) = ticks.next_with_batch(batch.clone());
+        let ticks = &[seed, hash];
+        crate::verify::<Hasher, _>(ticks, |_, _| root.clone()).unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+//! Streaming, bounded-memory verification of tick sequences too large (or too live) to hold
+//! in memory all at once.
+
+use crate::verify;
+use digest::{Digest, Output};
+use std::io::Read;
+
+/// The number of ticks buffered per chunk before being handed off to the parallel verifier.
+///
+/// Chosen to match the chunk size already exercised by this crate's own tests, giving Rayon
+/// enough work per chunk to keep the multi-core verification advantage while bounding memory.
+const CHUNK_LEN: usize = 1 << 16;
+
+/// Verifies a (potentially unbounded) sequence of ticks read from `ticks`, retaining only a
+/// bounded number of ticks in memory at a time.
+///
+/// Internally, ticks are buffered into fixed-size chunks of [`CHUNK_LEN`] and each chunk is
+/// verified via the existing, Rayon-parallel [`verify`], carrying the last tick of one chunk
+/// forward as the first tick of the next so that chunk boundaries are verified too. This lets
+/// an arbitrarily long ledger, or a live network feed, be verified with constant memory.
+///
+/// `data` is called with the *global* index of each tick pair, consistent with [`verify`].
+///
+/// # Returns
+///
+/// `Ok(())` if every tick is valid, or `Err(ix)` with the global index of the first tick pair
+/// that fails verification, numbered as in [`verify`]: pair `ix` is ticks `ix` and `ix + 1`,
+/// so a tampered tick at index `i > 0` is reported as `i - 1`.
+pub fn verify_stream<D, R, F>(mut ticks: R, data: F) -> Result<(), usize>
+where
+    D: Digest,
+    R: Iterator<Item = Output<D>>,
+    F: Sync + Fn(usize, &Output<D>) -> Output<D>,
+{
+    let mut offset = 0usize;
+    let mut carry = ticks.next();
+    loop {
+        let Some(first) = carry.take() else {
+            return Ok(());
+        };
+        let mut chunk = Vec::with_capacity(CHUNK_LEN + 1);
+        chunk.push(first);
+        while chunk.len() < CHUNK_LEN + 1 {
+            match ticks.next() {
+                Some(tick) => chunk.push(tick),
+                None => break,
+            }
+        }
+        if chunk.len() < 2 {
+            return Ok(());
+        }
+        let chunk_offset = offset;
+        verify::<D, _>(&chunk, |ix, hash| data(chunk_offset + ix, hash))
+            .map_err(|ix| chunk_offset + ix)?;
+        offset += chunk.len() - 1;
+        carry = chunk.pop();
+    }
+}
+
+/// An error produced by [`verify_reader`], distinguishing an IO failure on the underlying
+/// reader from an invalid tick at a given global index.
+#[derive(Debug)]
+pub enum VerifyReaderError {
+    /// Reading the next tick's digest from the reader failed.
+    Io(std::io::Error),
+    /// The tick pair at this global index failed verification, numbered as in
+    /// [`verify_stream`].
+    Invalid(usize),
+}
+
+impl std::fmt::Display for VerifyReaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read tick: {e}"),
+            Self::Invalid(ix) => write!(f, "tick {ix} failed verification"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyReaderError {}
+
+/// Verifies a sequence of ticks read as fixed-size digests from any [`Read`] implementation,
+/// using the same bounded-memory, chunked strategy as [`verify_stream`].
+pub fn verify_reader<D, R, F>(mut reader: R, data: F) -> Result<(), VerifyReaderError>
+where
+    D: Digest,
+    R: Read,
+    F: Sync + Fn(usize, &Output<D>) -> Output<D>,
+{
+    let mut io_err = None;
+    let iter = std::iter::from_fn(|| {
+        if io_err.is_some() {
+            return None;
+        }
+        let mut buf = Output::<D>::default();
+        match reader.read_exact(&mut buf) {
+            Ok(()) => Some(buf),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => None,
+            Err(e) => {
+                io_err = Some(e);
+                None
+            }
+        }
+    });
+    verify_stream::<D, _, F>(iter, data).map_err(VerifyReaderError::Invalid)?;
+    match io_err {
+        Some(e) => Err(VerifyReaderError::Io(e)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_verify_stream_matches_verify() {
+        type Hasher = sha2::Sha256;
+        let default_data = Output::<Hasher>::default();
+        let mut ticks = crate::ticks::<Hasher>(default_data);
+        let ticks: Vec<_> = std::iter::from_fn(|| Some(ticks.next()))
+            .take(3 * CHUNK_LEN + 7)
+            .collect();
+        verify_stream::<Hasher, _, _>(ticks.into_iter(), |_, _| default_data).unwrap();
+    }
+
+    #[test]
+    fn test_verify_stream_reports_global_index() {
+        type Hasher = sha2::Sha256;
+        let default_data = Output::<Hasher>::default();
+        let mut ticks = crate::ticks::<Hasher>(default_data);
+        let mut ticks: Vec<_> = std::iter::from_fn(|| Some(ticks.next()))
+            .take(2 * CHUNK_LEN + 5)
+            .collect();
+        let bad_ix = CHUNK_LEN + 3;
+        ticks[bad_ix] = Hasher::digest(b"tampered");
+        let err = verify_stream::<Hasher, _, _>(ticks.into_iter(), |_, _| default_data).unwrap_err();
+        assert_eq!(err, bad_ix - 1);
+    }
+
+    #[test]
+    fn test_verify_reader_roundtrip() {
+        type Hasher = sha2::Sha256;
+        let default_data = Output::<Hasher>::default();
+        let mut ticks = crate::ticks::<Hasher>(default_data);
+        let bytes: Vec<u8> = std::iter::from_fn(|| Some(ticks.next()))
+            .take(CHUNK_LEN + 11)
+            .flat_map(|h| h.to_vec())
+            .collect();
+        verify_reader::<Hasher, _, _>(&bytes[..], |_, _| default_data).unwrap();
+    }
+}